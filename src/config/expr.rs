@@ -0,0 +1,381 @@
+use serde::{de::Error as _, Deserialize, Deserializer};
+use std::collections::HashMap;
+use std::fmt;
+
+/// The variables an `Expr` can see while it's being evaluated (the
+/// functions it can call are a fixed set built into `Expr::eval`). Plant advancements fold over these to get a concrete
+/// number instead of being stuck with a fixed multiplier.
+#[derive(Debug, Clone, Default)]
+pub struct EvalContext {
+    vars: HashMap<String, f64>,
+}
+impl EvalContext {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with(mut self, name: &str, value: f64) -> Self {
+        self.vars.insert(name.to_string(), value);
+        self
+    }
+
+    pub fn get(&self, name: &str) -> Option<f64> {
+        self.vars.get(name).copied()
+    }
+
+    /// Baseline values for every variable a plant advancement formula
+    /// may reference. Used both to sanity-check a formula during
+    /// `Config::validate` (so a typo'd variable name is caught before
+    /// it's ever evaluated for real) and, via `or_defaults`, as the
+    /// fallback for any of these variables a real context leaves unset.
+    pub fn defaults() -> Self {
+        Self::new()
+            .with("base", 1.0)
+            .with("total_xp", 0.0)
+            .with("neighbors", 0.0)
+            .with("plant_size", 0.0)
+    }
+
+    /// Fills in `defaults()` for every variable `self` doesn't already
+    /// set, so a context built from only the plant state a caller has
+    /// on hand (e.g. just `neighbors`) still has every documented
+    /// variable available and won't fail evaluation with "unknown
+    /// variable" for the ones it left out.
+    pub fn or_defaults(self) -> Self {
+        let mut ctx = Self::defaults();
+        ctx.vars.extend(self.vars);
+        ctx
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnOp {
+    Neg,
+}
+
+/// A small formula language so advancement effects can depend on
+/// plant state instead of being a fixed constant, e.g.
+/// `"1.0 + 0.1 * neighbors"` or `"base * log(total_xp)"`.
+#[derive(Debug, Clone)]
+pub enum Expr {
+    Num(f64),
+    Var(String),
+    Unary(UnOp, Box<Expr>),
+    Binary(Box<Expr>, BinOp, Box<Expr>),
+    Call(String, Vec<Expr>),
+}
+impl Expr {
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let tokens = tokenize(input)?;
+        let mut parser = Parser { tokens, pos: 0 };
+        let expr = parser.parse_expr()?;
+        parser.expect_end()?;
+        Ok(expr)
+    }
+
+    pub fn eval(&self, ctx: &EvalContext) -> Result<f64, String> {
+        use Expr::*;
+
+        Ok(match self {
+            Num(n) => *n,
+            Var(name) => ctx
+                .get(name)
+                .ok_or_else(|| format!("unknown variable {:?}", name))?,
+            Unary(UnOp::Neg, e) => -e.eval(ctx)?,
+            Binary(l, op, r) => {
+                let l = l.eval(ctx)?;
+                let r = r.eval(ctx)?;
+                match op {
+                    BinOp::Add => l + r,
+                    BinOp::Sub => l - r,
+                    BinOp::Mul => l * r,
+                    BinOp::Div => l / r,
+                }
+            }
+            Call(name, args) => {
+                let args = args
+                    .iter()
+                    .map(|a| a.eval(ctx))
+                    .collect::<Result<Vec<_>, _>>()?;
+                match (name.as_str(), args.as_slice()) {
+                    ("min", [a, b]) => a.min(*b),
+                    ("max", [a, b]) => a.max(*b),
+                    ("log", [a]) => a.ln(),
+                    ("sqrt", [a]) => a.sqrt(),
+                    (name, args) => {
+                        return Err(format!(
+                            "unknown function {:?} taking {} argument(s)",
+                            name,
+                            args.len()
+                        ))
+                    }
+                }
+            }
+        })
+    }
+}
+impl<'de> Deserialize<'de> for Expr {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        Expr::parse(&s).map_err(D::Error::custom)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Num(f64),
+    Ident(String),
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    LParen,
+    RParen,
+    Comma,
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            ' ' | '\t' | '\n' | '\r' => i += 1,
+            '+' => {
+                tokens.push(Token::Plus);
+                i += 1;
+            }
+            '-' => {
+                tokens.push(Token::Minus);
+                i += 1;
+            }
+            '*' => {
+                tokens.push(Token::Star);
+                i += 1;
+            }
+            '/' => {
+                tokens.push(Token::Slash);
+                i += 1;
+            }
+            '(' => {
+                tokens.push(Token::LParen);
+                i += 1;
+            }
+            ')' => {
+                tokens.push(Token::RParen);
+                i += 1;
+            }
+            ',' => {
+                tokens.push(Token::Comma);
+                i += 1;
+            }
+            c if c.is_ascii_digit() || c == '.' => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                    i += 1;
+                }
+                let s: String = chars[start..i].iter().collect();
+                tokens.push(Token::Num(
+                    s.parse().map_err(|_| format!("invalid number {:?}", s))?,
+                ));
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+                tokens.push(Token::Ident(chars[start..i].iter().collect()));
+            }
+            c => return Err(format!("unexpected character {:?}", c)),
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn advance(&mut self) -> Option<Token> {
+        let t = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        t
+    }
+
+    fn expect_end(&self) -> Result<(), String> {
+        if self.pos == self.tokens.len() {
+            Ok(())
+        } else {
+            Err(format!("unexpected trailing token {:?}", self.tokens[self.pos]))
+        }
+    }
+
+    fn parse_expr(&mut self) -> Result<Expr, String> {
+        let mut left = self.parse_term()?;
+
+        loop {
+            match self.peek() {
+                Some(Token::Plus) => {
+                    self.advance();
+                    left = Expr::Binary(Box::new(left), BinOp::Add, Box::new(self.parse_term()?));
+                }
+                Some(Token::Minus) => {
+                    self.advance();
+                    left = Expr::Binary(Box::new(left), BinOp::Sub, Box::new(self.parse_term()?));
+                }
+                _ => break,
+            }
+        }
+
+        Ok(left)
+    }
+
+    fn parse_term(&mut self) -> Result<Expr, String> {
+        let mut left = self.parse_factor()?;
+
+        loop {
+            match self.peek() {
+                Some(Token::Star) => {
+                    self.advance();
+                    left = Expr::Binary(Box::new(left), BinOp::Mul, Box::new(self.parse_factor()?));
+                }
+                Some(Token::Slash) => {
+                    self.advance();
+                    left = Expr::Binary(Box::new(left), BinOp::Div, Box::new(self.parse_factor()?));
+                }
+                _ => break,
+            }
+        }
+
+        Ok(left)
+    }
+
+    fn parse_factor(&mut self) -> Result<Expr, String> {
+        if let Some(Token::Minus) = self.peek() {
+            self.advance();
+            return Ok(Expr::Unary(UnOp::Neg, Box::new(self.parse_factor()?)));
+        }
+
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> Result<Expr, String> {
+        match self.advance().ok_or("unexpected end of formula")? {
+            Token::Num(n) => Ok(Expr::Num(n)),
+            Token::Ident(name) => {
+                if let Some(Token::LParen) = self.peek() {
+                    self.advance();
+                    let mut args = Vec::new();
+                    if self.peek() != Some(&Token::RParen) {
+                        args.push(self.parse_expr()?);
+                        while self.peek() == Some(&Token::Comma) {
+                            self.advance();
+                            args.push(self.parse_expr()?);
+                        }
+                    }
+                    match self.advance() {
+                        Some(Token::RParen) => Ok(Expr::Call(name, args)),
+                        _ => Err("expected closing parenthesis after call arguments".to_string()),
+                    }
+                } else {
+                    Ok(Expr::Var(name))
+                }
+            }
+            Token::LParen => {
+                let inner = self.parse_expr()?;
+                match self.advance() {
+                    Some(Token::RParen) => Ok(inner),
+                    _ => Err("expected closing parenthesis".to_string()),
+                }
+            }
+            t => Err(format!("unexpected token {:?}", t)),
+        }
+    }
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expr::Num(n) => write!(f, "{}", n),
+            Expr::Var(name) => write!(f, "{}", name),
+            Expr::Unary(UnOp::Neg, e) => write!(f, "-{}", e),
+            Expr::Binary(l, op, r) => {
+                let op = match op {
+                    BinOp::Add => "+",
+                    BinOp::Sub => "-",
+                    BinOp::Mul => "*",
+                    BinOp::Div => "/",
+                };
+                write!(f, "({} {} {})", l, op, r)
+            }
+            Expr::Call(name, args) => {
+                write!(f, "{}(", name)?;
+                for (i, a) in args.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", a)?;
+                }
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+#[test]
+fn precedence_and_parens() {
+    // without parens, `*` binds tighter than `+`
+    assert_eq!(Expr::parse("1 + 2 * 3").unwrap().eval(&EvalContext::new()).unwrap(), 7.0);
+    assert_eq!(Expr::parse("(1 + 2) * 3").unwrap().eval(&EvalContext::new()).unwrap(), 9.0);
+}
+
+#[test]
+fn unary_minus() {
+    assert_eq!(Expr::parse("-2 + 3").unwrap().eval(&EvalContext::new()).unwrap(), 1.0);
+    assert_eq!(Expr::parse("2 * -(1 + 1)").unwrap().eval(&EvalContext::new()).unwrap(), -4.0);
+}
+
+#[test]
+fn variables_and_defaults() {
+    let formula = Expr::parse("1.0 + 0.1 * neighbors").unwrap();
+    assert_eq!(formula.eval(&EvalContext::defaults()).unwrap(), 1.0);
+    assert_eq!(formula.eval(&EvalContext::defaults().with("neighbors", 5.0)).unwrap(), 1.5);
+}
+
+#[test]
+fn functions() {
+    let ctx = EvalContext::new().with("base", 2.0);
+    assert_eq!(Expr::parse("max(1, 3)").unwrap().eval(&ctx).unwrap(), 3.0);
+    assert_eq!(Expr::parse("min(base, 1)").unwrap().eval(&ctx).unwrap(), 1.0);
+    assert_eq!(Expr::parse("sqrt(base * 2)").unwrap().eval(&ctx).unwrap(), 2.0);
+}
+
+#[test]
+fn unknown_variable_is_an_eval_error() {
+    let err = Expr::parse("totally_made_up").unwrap().eval(&EvalContext::defaults()).unwrap_err();
+    assert!(err.contains("totally_made_up"));
+}
+
+#[test]
+fn unknown_function_is_an_eval_error() {
+    let err = Expr::parse("frobnicate(1)").unwrap().eval(&EvalContext::new()).unwrap_err();
+    assert!(err.contains("frobnicate"));
+}
@@ -1,15 +1,107 @@
 use serde::{Deserialize, de::DeserializeOwned, Serialize};
 use std::fmt;
 
+mod expr;
+pub use expr::{EvalContext, Expr};
+
+mod tech_tree;
+pub use tech_tree::TechTree;
+
 #[derive(Debug, Clone)]
 pub enum ConfigError {
-    UnknownArchetypeName(String)
+    UnknownArchetypeName(String),
+    DuplicateArchetypeName {
+        name: String,
+        first_kind: &'static str,
+        second_kind: &'static str,
+    },
+    UnknownYieldResource {
+        plant: String,
+        resource: String,
+    },
+    UnknownRecipeInput {
+        plant: String,
+        makes: String,
+        input: String,
+    },
+    UnknownRecipeOutput {
+        plant: String,
+        makes: String,
+    },
+    UnknownGrowsInto {
+        seed: String,
+        grows_into: String,
+    },
+    CyclicSeedGrowth {
+        cycle: Vec<String>,
+    },
+    BadFormula {
+        plant: String,
+        formula: String,
+        message: String,
+    },
+    AmbiguousArchetypeName {
+        name: String,
+        candidates: Vec<String>,
+    },
+    LoadFailed(String),
+    CyclicRecipe {
+        cycle: Vec<String>,
+    },
 }
 impl fmt::Display for ConfigError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         use ConfigError::*;
         match self {
-            UnknownArchetypeName(name) => write!(f, "no archetype by the name of {:?}", name)
+            UnknownArchetypeName(name) => write!(f, "no archetype by the name of {:?}", name),
+            DuplicateArchetypeName { name, first_kind, second_kind } => write!(
+                f,
+                "archetype name {:?} is used by both a {} and a {} archetype",
+                name, first_kind, second_kind
+            ),
+            UnknownYieldResource { plant, resource } => write!(
+                f,
+                "Yield advancement for plant {:?} includes unknown resource {:?}",
+                plant, resource
+            ),
+            UnknownRecipeInput { plant, makes, input } => write!(
+                f,
+                "Crafting advancement for plant {:?} uses unknown resource {:?} in recipe for {:?}",
+                plant, input, makes
+            ),
+            UnknownRecipeOutput { plant, makes } => write!(
+                f,
+                "Crafting advancement for plant {:?} produces unknown resource {:?}",
+                plant, makes
+            ),
+            UnknownGrowsInto { seed, grows_into } => write!(
+                f,
+                "seed archetype {:?} claims it grows into unknown plant archetype {:?}",
+                seed, grows_into
+            ),
+            CyclicSeedGrowth { cycle } => write!(
+                f,
+                "seed growth forms a cycle: {}",
+                cycle.join(" -> ")
+            ),
+            BadFormula { plant, formula, message } => write!(
+                f,
+                "plant {:?} has a bad formula {:?}: {}",
+                plant, formula, message
+            ),
+            AmbiguousArchetypeName { name, candidates } => write!(
+                f,
+                "{:?} could refer to any of {}; use a fully-qualified name (e.g. {:?})",
+                name,
+                candidates.join(", "),
+                candidates[0],
+            ),
+            LoadFailed(message) => write!(f, "{}", message),
+            CyclicRecipe { cycle } => write!(
+                f,
+                "recipes form a cycle, so none of them are craftable from scratch: {}",
+                cycle.join(" -> ")
+            ),
         }
     }
 }
@@ -22,53 +114,280 @@ pub struct Config {
     pub possession_archetypes: Vec<Archetype>,
 }
 impl Config {
-    fn find_plant<S: AsRef<str>>(&self, name: &S) -> Result<&PlantArchetype, ConfigError> {
-        self.plant_archetypes
-            .iter()
-            .find(|x| name.as_ref() == x.name)
-            .ok_or(ConfigError::UnknownArchetypeName(name.as_ref().to_string()))
+    /// Resolves `name` as seen from `referencing_module`: a plant in
+    /// module `coffee.arabica` can just say `"seed"` for its own seed.
+    /// A plant in another module can also say `"seed"` as long as only
+    /// one module defines it; otherwise it has to spell out
+    /// `"coffee.arabica.seed"` (see `resolve_index`).
+    fn find_plant<S: AsRef<str>>(&self, referencing_module: &str, name: &S) -> Result<&PlantArchetype, ConfigError> {
+        let i = resolve_index(&self.plant_archetypes, referencing_module, name.as_ref(), |a| &a.module, |a| &a.name)?;
+        Ok(&self.plant_archetypes[i])
     }
-    fn find_possession<S: AsRef<str>>(&self, name: &S) -> Result<&Archetype, ConfigError> {
-        self.possession_archetypes
-            .iter()
-            .find(|x| name.as_ref() == x.name)
-            .ok_or(ConfigError::UnknownArchetypeName(name.as_ref().to_string()))
+    fn find_possession<S: AsRef<str>>(&self, referencing_module: &str, name: &S) -> Result<&Archetype, ConfigError> {
+        let i = resolve_index(&self.possession_archetypes, referencing_module, name.as_ref(), |a| &a.module, |a| &a.name)?;
+        Ok(&self.possession_archetypes[i])
+    }
+    fn find_possession_handle<S: AsRef<str>>(&self, referencing_module: &str, name: &S) -> Result<ArchetypeHandle, ConfigError> {
+        resolve_index(&self.possession_archetypes, referencing_module, name.as_ref(), |a| &a.module, |a| &a.name)
+    }
+
+    fn read_json<T: DeserializeOwned>(name: &'static str) -> Result<T, ConfigError> {
+        let path = format!(concat!(env!("CARGO_MANIFEST_DIR"), "/config/{}.json"), name);
+        let contents = std::fs::read_to_string(&path)
+            .map_err(|e| ConfigError::LoadFailed(format!("opening {}: {}", name, e)))?;
+        serde_json::from_str(&contents)
+            .map_err(|e| ConfigError::LoadFailed(format!("parsing {}: {}", name, e)))
+    }
+
+    /// Reads all four config files from disk without validating them.
+    fn load() -> Result<Self, ConfigError> {
+        Ok(Config {
+            special_users: Self::read_json("special_users")?,
+            hackstead_advancements: Self::read_json("hackstead_advancements")?,
+            plant_archetypes: Self::read_json("plant_archetypes")?,
+            possession_archetypes: Self::read_json("possession_archetypes")?,
+        })
+    }
+
+    /// Loads from disk and validates, collecting every problem instead
+    /// of panicking. Used by the hot-reload worker in [`reload`] for
+    /// both its initial load and every later [`reload::request_reload`],
+    /// which needs to keep the previous config alive on failure rather
+    /// than taking down the process.
+    pub fn load_checked() -> Result<Self, Vec<ConfigError>> {
+        let config = Self::load().map_err(|e| vec![e])?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Walks every archetype, advancement, and recipe, collecting every
+    /// problem found rather than stopping at the first one. Call this
+    /// once a `Config` is fully parsed, before trusting any of its
+    /// `find_*` lookups to succeed.
+    pub fn validate(&self) -> Result<(), Vec<ConfigError>> {
+        let mut errors = Vec::new();
+
+        self.validate_unique_names(&mut errors);
+
+        for a in self.possession_archetypes.iter() {
+            if let ArchetypeKind::Seed(sa) = &a.kind {
+                if let Err(e) = self.find_plant(&a.module, &sa.grows_into) {
+                    errors.push(match e {
+                        e @ ConfigError::AmbiguousArchetypeName { .. } => e,
+                        _ => ConfigError::UnknownGrowsInto {
+                            seed: qualified_name(&a.module, &a.name),
+                            grows_into: sa.grows_into.clone(),
+                        },
+                    });
+                }
+            }
+        }
+
+        for arch in self.plant_archetypes.iter() {
+            for adv in arch.advancements.all_ref().iter() {
+                use PlantAdvancementKind::*;
+
+                match &adv.kind {
+                    Xp { formula } | YieldSpeed { formula } | YieldNeighboringSize { formula } => {
+                        if let Err(message) = formula.eval(&EvalContext::defaults()) {
+                            errors.push(ConfigError::BadFormula {
+                                plant: arch.name.clone(),
+                                formula: formula.to_string(),
+                                message,
+                            });
+                        }
+                    }
+                    Yield { resources } => {
+                        for (_, resource) in resources.iter() {
+                            if let Err(e) = self.find_possession(&arch.module, resource) {
+                                errors.push(match e {
+                                    e @ ConfigError::AmbiguousArchetypeName { .. } => e,
+                                    _ => ConfigError::UnknownYieldResource {
+                                        plant: qualified_name(&arch.module, &arch.name),
+                                        resource: resource.clone(),
+                                    },
+                                });
+                            }
+                        }
+                    }
+                    Craft { recipes } => {
+                        for Recipe { makes, needs } in recipes.iter() {
+                            if let Err(e) = self.find_possession(&arch.module, makes) {
+                                errors.push(match e {
+                                    e @ ConfigError::AmbiguousArchetypeName { .. } => e,
+                                    _ => ConfigError::UnknownRecipeOutput {
+                                        plant: qualified_name(&arch.module, &arch.name),
+                                        makes: makes.clone(),
+                                    },
+                                });
+                            }
+                            for (_, input) in needs.iter() {
+                                if let Err(e) = self.find_possession(&arch.module, input) {
+                                    errors.push(match e {
+                                        e @ ConfigError::AmbiguousArchetypeName { .. } => e,
+                                        _ => ConfigError::UnknownRecipeInput {
+                                            plant: qualified_name(&arch.module, &arch.name),
+                                            makes: makes.clone(),
+                                            input: input.clone(),
+                                        },
+                                    });
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        if let Some(cycle) = self.find_seed_growth_cycle() {
+            errors.push(ConfigError::CyclicSeedGrowth { cycle });
+        }
+
+        if let Some(e) = TechTree::build(self).find_recipe_cycle(self) {
+            errors.push(e);
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
     }
-    fn find_possession_handle<S: AsRef<str>>(&self, name: &S) -> Result<ArchetypeHandle, ConfigError> {
-        self.possession_archetypes
+
+    fn validate_unique_names(&self, errors: &mut Vec<ConfigError>) {
+        let mut seen: std::collections::HashMap<(&str, &str), &'static str> = std::collections::HashMap::new();
+
+        for (module, name, kind) in self
+            .plant_archetypes
             .iter()
-            .position(|x| name.as_ref() == x.name)
-            .ok_or(ConfigError::UnknownArchetypeName(name.as_ref().to_string()))
+            .map(|a| (a.module.as_str(), a.name.as_str(), "plant"))
+            .chain(self.possession_archetypes.iter().map(|a| (a.module.as_str(), a.name.as_str(), "possession")))
+        {
+            if let Some(first_kind) = seen.get(&(module, name)) {
+                errors.push(ConfigError::DuplicateArchetypeName {
+                    name: qualified_name(module, name),
+                    first_kind,
+                    second_kind: kind,
+                });
+            } else {
+                seen.insert((module, name), kind);
+            }
+        }
+    }
+
+    /// A seed grows into a plant, whose yields may themselves be seeds;
+    /// if following that chain ever returns to a seed already on the
+    /// path, nothing could ever finish growing. Returns the offending
+    /// chain of fully-qualified names, starting and ending with the
+    /// repeated seed.
+    fn find_seed_growth_cycle(&self) -> Option<Vec<String>> {
+        for start in self.possession_archetypes.iter() {
+            if let ArchetypeKind::Seed(_) = &start.kind {
+                let mut path = vec![qualified_name(&start.module, &start.name)];
+                if self.walk_seed_growth(start, &mut path) {
+                    return Some(path);
+                }
+            }
+        }
+        None
+    }
+
+    fn walk_seed_growth(&self, seed: &Archetype, path: &mut Vec<String>) -> bool {
+        let sa = match &seed.kind {
+            ArchetypeKind::Seed(sa) => sa,
+            _ => return false,
+        };
+        let plant = match self.find_plant(&seed.module, &sa.grows_into) {
+            Ok(p) => p,
+            Err(_) => return false,
+        };
+
+        for adv in plant.advancements.all_ref().iter() {
+            if let PlantAdvancementKind::Yield { resources } = &adv.kind {
+                for (_, resource) in resources.iter() {
+                    if let Ok(possession) = self.find_possession(&plant.module, resource) {
+                        if let ArchetypeKind::Seed(_) = &possession.kind {
+                            let qname = qualified_name(&possession.module, &possession.name);
+                            if qname == path[0] {
+                                path.push(qname);
+                                return true;
+                            }
+                            if !path.contains(&qname) {
+                                path.push(qname);
+                                if self.walk_seed_growth(possession, path) {
+                                    return true;
+                                }
+                                path.pop();
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        false
     }
 }
 
 pub type ArchetypeHandle = usize;
 
-lazy_static::lazy_static! {
-    pub static ref CONFIG: Config = {
-        pub fn f<T: DeserializeOwned>(p: &'static str) -> T {
-            serde_json::from_str(
-                &std::fs::read_to_string(format!(
-                    concat!(
-                        env!("CARGO_MANIFEST_DIR"),
-                        "/config/{}.json",
-                    ),
-                    p
-                ))
-                .unwrap_or_else(|e| panic!("opening {}: {}", p, e))
-            )
-            .unwrap_or_else(|e| panic!("parsing {}: {}", p, e))
-        }
+/// The name a reference to this archetype would need to use from
+/// outside its own module.
+fn qualified_name(module: &str, name: &str) -> String {
+    if module.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}.{}", module, name)
+    }
+}
 
-        Config {
-            special_users: f("special_users"),
-            hackstead_advancements: f("hackstead_advancements"),
-            plant_archetypes: f("plant_archetypes"),
-            possession_archetypes: f("possession_archetypes"),
-        }
-    };
+/// Resolves `name` to the index of the single candidate it refers to.
+/// A name containing a `.` is treated as fully-qualified (`module.name`).
+/// Otherwise, a match within `referencing_module` wins outright; failing
+/// that, the name must be unambiguous across every module.
+fn resolve_index<T>(
+    candidates: &[T],
+    referencing_module: &str,
+    name: &str,
+    module_of: impl Fn(&T) -> &str,
+    name_of: impl Fn(&T) -> &str,
+) -> Result<ArchetypeHandle, ConfigError> {
+    if let Some((module, short_name)) = name.rsplit_once('.') {
+        return candidates
+            .iter()
+            .position(|x| module_of(x) == module && name_of(x) == short_name)
+            .ok_or_else(|| ConfigError::UnknownArchetypeName(name.to_string()));
+    }
+
+    if let Some(i) = candidates
+        .iter()
+        .position(|x| module_of(x) == referencing_module && name_of(x) == name)
+    {
+        return Ok(i);
+    }
+
+    let mut matches = candidates
+        .iter()
+        .enumerate()
+        .filter(|(_, x)| name_of(x) == name);
+
+    match (matches.next(), matches.next()) {
+        (None, _) => Err(ConfigError::UnknownArchetypeName(name.to_string())),
+        (Some((i, _)), None) => Ok(i),
+        (Some((fi, first)), Some((si, second))) => Err(ConfigError::AmbiguousArchetypeName {
+            name: name.to_string(),
+            candidates: std::iter::once((fi, first))
+                .chain(std::iter::once((si, second)))
+                .chain(matches)
+                .map(|(_, x)| qualified_name(module_of(x), name_of(x)))
+                .collect(),
+        }),
+    }
 }
 
+mod reload;
+pub use reload::{current, progress, request_reload, stop, ConfigCommand, Progress};
+
 #[derive(Deserialize, Debug, Clone)]
 pub enum HacksteadAdvancementKind {
     Land { pieces: usize },
@@ -80,8 +399,8 @@ pub struct HacksteadAdvancementSum {
 impl AdvancementSum for HacksteadAdvancementSum {
     type Kind = HacksteadAdvancementKind;
 
-    fn new(unlocked: &[Advancement<Self>]) -> Self {
-        Self { 
+    fn new(unlocked: &[Advancement<Self>], _ctx: &EvalContext, _module: &str, _config: &Config) -> Self {
+        Self {
             land: unlocked.iter().map(|k| match k.kind {
                 HacksteadAdvancementKind::Land { pieces } => pieces
             }).sum()
@@ -108,12 +427,19 @@ pub enum ArchetypeKind {
 }
 #[derive(Deserialize, Debug, Clone)]
 pub struct Archetype {
+    /// Dotted module path this archetype belongs to, e.g. `"coffee.arabica"`.
+    /// Empty means the archetype isn't namespaced and must have a
+    /// globally unique `name`.
+    #[serde(default)]
+    pub module: String,
     pub name: String,
     pub kind: ArchetypeKind,
 }
 
 #[derive(Deserialize, Debug, Clone)]
 pub struct PlantArchetype {
+    #[serde(default)]
+    pub module: String,
     pub name: String,
     pub advancements: AdvancementSet<PlantAdvancementSum>,
 }
@@ -127,9 +453,9 @@ pub struct Recipe<Handle> {
 }
 #[derive(Deserialize, Debug, Clone)]
 pub enum PlantAdvancementKind {
-    Xp { multiplier: f32 },
-    YieldSpeed { multiplier: f32 },
-    YieldNeighboringSize { multiplier: f32 },
+    Xp { formula: Expr },
+    YieldSpeed { formula: Expr },
+    YieldNeighboringSize { formula: Expr },
     Yield { resources: Vec<(f32, String)> },
     Craft { recipes: Vec<Recipe<String>> },
 }
@@ -144,9 +470,19 @@ pub struct PlantAdvancementSum {
 impl AdvancementSum for PlantAdvancementSum {
     type Kind = PlantAdvancementKind;
 
-    fn new(unlocked: &[Advancement<Self>]) -> Self {
+    fn new(unlocked: &[Advancement<Self>], ctx: &EvalContext, module: &str, config: &Config) -> Self {
         use PlantAdvancementKind::*;
 
+        // `ctx` is always `or_defaults()`-filled by `AdvancementSet::sum_with`/
+        // `max_with` before it gets here, and formulas are checked against a
+        // dummy context during `Config::validate`, so any failure here means
+        // validation was skipped, not a bad config or a partial `ctx`.
+        let eval = |formula: &Expr| -> f32 {
+            formula
+                .eval(ctx)
+                .unwrap_or_else(|e| panic!("unvalidated formula {}: {}", formula, e)) as f32
+        };
+
         let mut sum = PlantAdvancementSum {
             xp_multiplier: 1.0,
             yield_speed_multiplier: 1.0,
@@ -156,18 +492,18 @@ impl AdvancementSum for PlantAdvancementSum {
 
         for k in unlocked.iter() {
             match &k.kind {
-                Xp { multiplier } => {
-                    sum.xp_multiplier *= multiplier;
+                Xp { formula } => {
+                    sum.xp_multiplier *= eval(formula);
                 }
-                YieldSpeed { multiplier } => {
-                    sum.yield_speed_multiplier *= multiplier;
+                YieldSpeed { formula } => {
+                    sum.yield_speed_multiplier *= eval(formula);
                 },
                 YieldNeighboringSize { .. } => {},
                 Yield { resources } => {
                     sum.yields.append(
                         &mut resources
                             .iter()
-                            .map(|(c, s)| Ok((*c, CONFIG.find_possession_handle(s)?)))
+                            .map(|(c, s)| Ok((*c, config.find_possession_handle(module, s)?)))
                             .collect::<Result<Vec<_>, ConfigError>>()
                             .expect("couldn't find archetype for advancement yield")
                     )
@@ -178,9 +514,9 @@ impl AdvancementSum for PlantAdvancementSum {
                             .iter()
                             .map(|r| {
                                 Ok(Recipe {
-                                    makes: CONFIG.find_possession_handle(&r.makes)?,
+                                    makes: config.find_possession_handle(module, &r.makes)?,
                                     needs: r.needs.iter().map(|(c, s)| {
-                                        Ok((*c, CONFIG.find_possession_handle(s)?))
+                                        Ok((*c, config.find_possession_handle(module, s)?))
                                     })
                                     .collect::<Result<Vec<_>, ConfigError>>()?
                                 })
@@ -199,7 +535,11 @@ impl AdvancementSum for PlantAdvancementSum {
 pub trait AdvancementSum: DeserializeOwned + fmt::Debug {
     type Kind: DeserializeOwned + fmt::Debug + Clone;
 
-    fn new(unlocked: &[Advancement<Self>]) -> Self;
+    /// `config` is the live config `unlocked`'s handles should be
+    /// resolved against -- whatever `reload::current()` returns at the
+    /// time, since a reload swaps in a new `Config` whose
+    /// `possession_archetypes` may have shifted indices.
+    fn new(unlocked: &[Advancement<Self>], ctx: &EvalContext, module: &str, config: &Config) -> Self;
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -223,12 +563,40 @@ impl<S: AdvancementSum> AdvancementSet<S> {
         self.rest
     }
 
-    pub fn sum(&self, xp: u64) -> S {
-        S::new(&self.rest[0..self.current_position(xp)])
+    /// Like `all`, but borrows instead of consuming the set.
+    pub fn all_ref(&self) -> Vec<&Advancement<S>> {
+        std::iter::once(&self.base).chain(self.rest.iter()).collect()
+    }
+
+    pub fn sum(&self, xp: u64, module: &str, config: &Config) -> S {
+        self.sum_with(xp, module, EvalContext::defaults(), config)
     }
 
-    pub fn max(&self) -> S {
-        S::new(&self.rest)
+    /// Like `sum`, but lets the caller seed the formula context with
+    /// real plant state (`neighbors`, `plant_size`, ...) instead of
+    /// leaving it at `EvalContext::defaults()`. Whatever `ctx` doesn't
+    /// set is filled in from `defaults()` (see `EvalContext::or_defaults`),
+    /// and `total_xp` is always overwritten with `xp` regardless of
+    /// what `ctx` already has for it.
+    ///
+    /// `config` must be the same `Config` `self` was loaded from --
+    /// it's what resolves the handles `unlocked`'s advancements refer
+    /// to, so a stale `Config` here means stale `ArchetypeHandle`s.
+    pub fn sum_with(&self, xp: u64, module: &str, ctx: EvalContext, config: &Config) -> S {
+        let ctx = ctx.with("total_xp", xp as f64).or_defaults();
+        S::new(&self.rest[0..self.current_position(xp)], &ctx, module, config)
+    }
+
+    pub fn max(&self, module: &str, config: &Config) -> S {
+        self.max_with(module, EvalContext::defaults(), config)
+    }
+
+    /// Like `max`, but lets the caller seed the formula context with
+    /// real plant state; see `sum_with`.
+    pub fn max_with(&self, module: &str, ctx: EvalContext, config: &Config) -> S {
+        let xp = self.rest.last().map(|a| a.xp).unwrap_or(0);
+        let ctx = ctx.with("total_xp", xp as f64).or_defaults();
+        S::new(&self.rest, &ctx, module, config)
     }
 
     pub fn current(&self, xp: u64) -> &Advancement<S> {
@@ -242,61 +610,15 @@ impl<S: AdvancementSum> AdvancementSet<S> {
 
 
 #[test]
-/// In the CONFIG, you can specify the names of archetypes.
+/// In the config files, you can specify the names of archetypes.
 /// If you're Rishi, you might spell one of those names wrong.
-/// This test helps you make sure you didn't do that.
-fn archetype_name_matches() {
-    for a in CONFIG.possession_archetypes.iter() {
-        match &a.kind {
-            ArchetypeKind::Seed(sa) => assert!(
-                CONFIG.find_plant(&sa.grows_into).is_ok(),
-                "seed archetype {:?} claims it grows into unknown plant archetype {:?}",
-                a.name,
-                sa.grows_into,
-            ),
-            _ => {}
-        }
-    }
-
-    for arch in CONFIG.plant_archetypes.iter().cloned() {
-        for adv in arch.advancements.all().iter() {
-            use PlantAdvancementKind::*;
-
-            match &adv.kind {
-                Yield { resources } => {
-                    for (_, item_name) in resources.iter() {
-                        assert!(
-                            CONFIG.find_possession(item_name).is_ok(),
-                            "Yield advancement {:?} for plant {:?} includes unknown resource {:?}",
-                            adv.title,
-                            arch.name,
-                            item_name,
-                        )
-                    }
-                }
-                Craft { recipes } => {
-                    for Recipe { makes, needs } in recipes.iter() {
-                        assert!(
-                            CONFIG.find_possession(makes).is_ok(),
-                            "Crafting advancement {:?} for plant {:?} produces unknown resource {:?}",
-                            adv.title,
-                            arch.name,
-                            makes,
-                        );
-                        for (_, resource) in needs.iter() {
-                            assert!(
-                                CONFIG.find_possession(resource).is_ok(),
-                                "Crafting advancement {:?} for plant {:?} uses unknown resource {:?} in recipe for {:?}",
-                                adv.title,
-                                arch.name,
-                                resource,
-                                makes
-                            )
-                        }
-                    }
-                }
-                _ => {}
-            }
+/// This test makes sure you didn't, and prints every mistake at once
+/// instead of stopping at the first.
+fn config_validates() {
+    if let Err(errors) = reload::current().validate() {
+        for e in &errors {
+            eprintln!("config error: {}", e);
         }
+        panic!("config failed validation with {} error(s), see above", errors.len());
     }
 }
@@ -0,0 +1,92 @@
+use super::{Config, ConfigError};
+use arc_swap::{ArcSwap, Guard};
+use std::sync::mpsc::{channel, Sender};
+use std::sync::Arc;
+
+/// Messages the reload worker understands, mirroring a flycheck-style
+/// actor: ask it to redo its work, or ask it to stop for good.
+pub enum ConfigCommand {
+    Reload,
+    Stop,
+}
+
+/// Where the most recent `Reload` left off.
+#[derive(Debug, Clone)]
+pub enum Progress {
+    Reloading,
+    Loaded,
+    Failed(Vec<ConfigError>),
+}
+
+struct Worker {
+    live: Arc<ArcSwap<Config>>,
+    progress: Arc<ArcSwap<Progress>>,
+    commands: Sender<ConfigCommand>,
+}
+
+lazy_static::lazy_static! {
+    static ref WORKER: Worker = Worker::spawn();
+}
+
+impl Worker {
+    fn spawn() -> Self {
+        let live = Arc::new(ArcSwap::from_pointee(
+            Config::load_checked().unwrap_or_else(|errors| {
+                for e in &errors {
+                    eprintln!("config error: {}", e);
+                }
+                panic!("config failed validation with {} error(s), see above", errors.len());
+            }),
+        ));
+        let progress = Arc::new(ArcSwap::from_pointee(Progress::Loaded));
+        let (commands, rx) = channel();
+
+        {
+            let live = live.clone();
+            let progress = progress.clone();
+            std::thread::spawn(move || {
+                for cmd in rx {
+                    match cmd {
+                        ConfigCommand::Reload => {
+                            progress.store(Arc::new(Progress::Reloading));
+                            progress.store(Arc::new(match Config::load_checked() {
+                                Ok(config) => {
+                                    live.store(Arc::new(config));
+                                    Progress::Loaded
+                                }
+                                Err(errors) => Progress::Failed(errors),
+                            }));
+                        }
+                        ConfigCommand::Stop => break,
+                    }
+                }
+            });
+        }
+
+        Worker { live, progress, commands }
+    }
+}
+
+/// The latest config that passed validation. Reads never block on a
+/// reload in progress; a failed reload leaves this pointing at
+/// whatever last loaded cleanly.
+pub fn current() -> Guard<Arc<Config>> {
+    WORKER.live.load()
+}
+
+/// The outcome of the most recently requested reload.
+pub fn progress() -> Guard<Arc<Progress>> {
+    WORKER.progress.load()
+}
+
+/// Asks the reload worker to re-read and re-validate the config files
+/// on disk. Returns immediately; check [`progress`] for the outcome.
+pub fn request_reload() {
+    let _ = WORKER.commands.send(ConfigCommand::Reload);
+}
+
+/// Stops the reload worker thread. Mostly useful for tests and clean
+/// shutdown; `current()` keeps returning the last loaded config.
+pub fn stop() {
+    let _ = WORKER.commands.send(ConfigCommand::Stop);
+}
@@ -0,0 +1,339 @@
+use super::{ArchetypeHandle, ArchetypeKind, Config, ConfigError, PlantAdvancementKind};
+use std::collections::HashSet;
+
+/// The production graph implied by `SeedArchetype::grows_into`,
+/// `Yield`, and `Craft` advancements: what needs to already exist
+/// before something else can. Nodes are possession `ArchetypeHandle`s.
+#[derive(Debug, Clone)]
+pub struct TechTree {
+    node_count: usize,
+    /// (seed, yielded resource) -- reachable the moment the seed is.
+    growth_edges: Vec<(ArchetypeHandle, ArchetypeHandle)>,
+    /// (all inputs, output) -- reachable once every input is.
+    recipes: Vec<(Vec<ArchetypeHandle>, ArchetypeHandle)>,
+}
+impl TechTree {
+    /// Builds the graph from a `Config`. Unresolved names are skipped
+    /// here rather than erroring -- `Config::validate` already reports
+    /// those separately, and a tech tree with a few dangling references
+    /// is still useful for everything else it can tell you.
+    pub fn build(config: &Config) -> Self {
+        let mut growth_edges = Vec::new();
+        let mut recipes = Vec::new();
+
+        for (seed_handle, a) in config.possession_archetypes.iter().enumerate() {
+            if let ArchetypeKind::Seed(sa) = &a.kind {
+                if let Ok(plant) = config.find_plant(&a.module, &sa.grows_into) {
+                    for adv in plant.advancements.all_ref() {
+                        if let PlantAdvancementKind::Yield { resources } = &adv.kind {
+                            for (_, resource) in resources {
+                                if let Ok(yielded) = config.find_possession_handle(&plant.module, resource) {
+                                    growth_edges.push((seed_handle, yielded));
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        for plant in config.plant_archetypes.iter() {
+            for adv in plant.advancements.all_ref() {
+                if let PlantAdvancementKind::Craft { recipes: plant_recipes } = &adv.kind {
+                    for r in plant_recipes {
+                        let makes = match config.find_possession_handle(&plant.module, &r.makes) {
+                            Ok(h) => h,
+                            Err(_) => continue,
+                        };
+                        let needs: Vec<ArchetypeHandle> = r
+                            .needs
+                            .iter()
+                            .filter_map(|(_, name)| config.find_possession_handle(&plant.module, name).ok())
+                            .collect();
+                        if needs.len() == r.needs.len() {
+                            recipes.push((needs, makes));
+                        }
+                    }
+                }
+            }
+        }
+
+        TechTree {
+            node_count: config.possession_archetypes.len(),
+            growth_edges,
+            recipes,
+        }
+    }
+
+    /// Flags any recipe cycle (A needs B, B needs A, directly or
+    /// transitively), which would make every item in the cycle
+    /// uncraftable from scratch. Seed/yield growth isn't considered
+    /// here -- that's `Config::find_seed_growth_cycle`'s job.
+    pub fn find_recipe_cycle(&self, config: &Config) -> Option<ConfigError> {
+        #[derive(Clone, Copy, PartialEq)]
+        enum Mark {
+            Visiting,
+            Done,
+        }
+
+        let mut edges = vec![Vec::new(); self.node_count];
+        for (needs, makes) in &self.recipes {
+            for &need in needs {
+                edges[need].push(*makes);
+            }
+        }
+
+        let mut marks = vec![None; self.node_count];
+        let mut path = Vec::new();
+
+        fn visit(
+            node: ArchetypeHandle,
+            edges: &[Vec<ArchetypeHandle>],
+            marks: &mut [Option<Mark>],
+            path: &mut Vec<ArchetypeHandle>,
+        ) -> Option<Vec<ArchetypeHandle>> {
+            match marks[node] {
+                Some(Mark::Done) => return None,
+                Some(Mark::Visiting) => {
+                    let start = path.iter().position(|&n| n == node).unwrap_or(0);
+                    let mut cycle = path[start..].to_vec();
+                    cycle.push(node);
+                    return Some(cycle);
+                }
+                None => {}
+            }
+
+            marks[node] = Some(Mark::Visiting);
+            path.push(node);
+
+            for &next in &edges[node] {
+                if let Some(cycle) = visit(next, edges, marks, path) {
+                    return Some(cycle);
+                }
+            }
+
+            path.pop();
+            marks[node] = Some(Mark::Done);
+            None
+        }
+
+        for node in 0..self.node_count {
+            if marks[node].is_none() {
+                if let Some(cycle) = visit(node, &edges, &mut marks, &mut path) {
+                    return Some(ConfigError::CyclicRecipe {
+                        cycle: cycle
+                            .into_iter()
+                            .map(|h| super::qualified_name(&config.possession_archetypes[h].module, &config.possession_archetypes[h].name))
+                            .collect(),
+                    });
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Everything reachable starting from having only `start` on hand,
+    /// computed by repeated relaxation: a seed's yields become
+    /// reachable the moment the seed is, and a recipe's output becomes
+    /// reachable once every one of its inputs is -- run to a fixpoint
+    /// since later unlocks can themselves unlock earlier ones' recipes.
+    pub fn reachable_from(&self, start: &[ArchetypeHandle]) -> HashSet<ArchetypeHandle> {
+        let mut reachable: HashSet<ArchetypeHandle> = start.iter().copied().collect();
+
+        loop {
+            let mut changed = false;
+
+            for &(seed, yielded) in &self.growth_edges {
+                if reachable.contains(&seed) && reachable.insert(yielded) {
+                    changed = true;
+                }
+            }
+
+            for (needs, makes) in &self.recipes {
+                if !reachable.contains(makes) && needs.iter().all(|n| reachable.contains(n)) {
+                    reachable.insert(*makes);
+                    changed = true;
+                }
+            }
+
+            if !changed {
+                break;
+            }
+        }
+
+        reachable
+    }
+
+    /// Archetypes that can never be made, starting from whatever is
+    /// never itself produced by a seed's growth or a recipe (the raw
+    /// materials everything else is built from).
+    pub fn unreachable(&self) -> HashSet<ArchetypeHandle> {
+        let produced: HashSet<ArchetypeHandle> = self
+            .growth_edges
+            .iter()
+            .map(|(_, yielded)| *yielded)
+            .chain(self.recipes.iter().map(|(_, makes)| *makes))
+            .collect();
+
+        let roots: Vec<ArchetypeHandle> = (0..self.node_count).filter(|h| !produced.contains(h)).collect();
+        let reachable = self.reachable_from(&roots);
+
+        (0..self.node_count).filter(|h| !reachable.contains(h)).collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use super::super::{Advancement, AdvancementSet, Archetype, PlantArchetype, Recipe, SeedArchetype};
+
+    fn advancement(kind: PlantAdvancementKind) -> Advancement<super::super::PlantAdvancementSum> {
+        Advancement {
+            kind,
+            xp: 0,
+            title: String::new(),
+            description: String::new(),
+            achiever_title: String::new(),
+        }
+    }
+
+    fn plant(module: &str, name: &str, kinds: Vec<PlantAdvancementKind>) -> PlantArchetype {
+        let mut advancements = kinds.into_iter().map(advancement);
+        let base = advancements.next().expect("plant needs at least one advancement");
+        PlantArchetype {
+            module: module.to_string(),
+            name: name.to_string(),
+            advancements: AdvancementSet { base, rest: advancements.collect() },
+        }
+    }
+
+    fn possession(module: &str, name: &str, kind: ArchetypeKind) -> Archetype {
+        Archetype { module: module.to_string(), name: name.to_string(), kind }
+    }
+
+    fn recipe(needs: &[&str], makes: &str) -> Recipe<String> {
+        Recipe {
+            needs: needs.iter().map(|n| (1, n.to_string())).collect(),
+            makes: makes.to_string(),
+        }
+    }
+
+    fn config(plant_archetypes: Vec<PlantArchetype>, possession_archetypes: Vec<Archetype>) -> Config {
+        Config {
+            special_users: vec![],
+            hackstead_advancements: AdvancementSet {
+                base: Advancement {
+                    kind: super::super::HacksteadAdvancementKind::Land { pieces: 0 },
+                    xp: 0,
+                    title: String::new(),
+                    description: String::new(),
+                    achiever_title: String::new(),
+                },
+                rest: vec![],
+            },
+            plant_archetypes,
+            possession_archetypes,
+        }
+    }
+
+    fn keepsake(module: &str, name: &str) -> Archetype {
+        possession(module, name, ArchetypeKind::Keepsake(super::super::KeepsakeArchetype))
+    }
+
+    #[test]
+    fn finds_a_direct_recipe_cycle() {
+        let config = config(
+            vec![plant(
+                "",
+                "p",
+                vec![
+                    PlantAdvancementKind::Craft { recipes: vec![recipe(&["b"], "a")] },
+                    PlantAdvancementKind::Craft { recipes: vec![recipe(&["a"], "b")] },
+                ],
+            )],
+            vec![keepsake("", "a"), keepsake("", "b")],
+        );
+
+        let cycle = TechTree::build(&config)
+            .find_recipe_cycle(&config)
+            .expect("a <-> b should be flagged as a cycle");
+        match cycle {
+            ConfigError::CyclicRecipe { cycle } => {
+                assert!(cycle.contains(&"a".to_string()));
+                assert!(cycle.contains(&"b".to_string()));
+            }
+            other => panic!("expected CyclicRecipe, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn acyclic_recipes_are_not_flagged() {
+        let config = config(
+            vec![plant(
+                "",
+                "p",
+                vec![PlantAdvancementKind::Craft { recipes: vec![recipe(&["a"], "b")] }],
+            )],
+            vec![keepsake("", "a"), keepsake("", "b")],
+        );
+
+        assert!(TechTree::build(&config).find_recipe_cycle(&config).is_none());
+    }
+
+    #[test]
+    fn reachable_from_follows_growth_and_recipes_to_a_fixpoint() {
+        let config = config(
+            vec![plant(
+                "",
+                "p",
+                vec![
+                    PlantAdvancementKind::Yield { resources: vec![(1.0, "raw".to_string())] },
+                    PlantAdvancementKind::Craft { recipes: vec![recipe(&["raw"], "tool")] },
+                    PlantAdvancementKind::Craft { recipes: vec![recipe(&["tool", "raw"], "gadget")] },
+                ],
+            )],
+            vec![
+                possession("", "seed", ArchetypeKind::Seed(SeedArchetype { grows_into: "p".to_string() })),
+                keepsake("", "raw"),
+                keepsake("", "tool"),
+                keepsake("", "gadget"),
+            ],
+        );
+
+        let tree = TechTree::build(&config);
+        let seed = config.find_possession_handle("", &"seed").unwrap();
+        let raw = config.find_possession_handle("", &"raw").unwrap();
+        let tool = config.find_possession_handle("", &"tool").unwrap();
+        let gadget = config.find_possession_handle("", &"gadget").unwrap();
+
+        let reachable = tree.reachable_from(&[seed]);
+        assert_eq!(reachable, [seed, raw, tool, gadget].into_iter().collect());
+        assert!(tree.unreachable().is_empty());
+    }
+
+    #[test]
+    fn unreachable_catches_a_cluster_with_no_root() {
+        let config = config(
+            vec![plant(
+                "",
+                "p",
+                vec![
+                    PlantAdvancementKind::Craft { recipes: vec![recipe(&["a"], "b")] },
+                    PlantAdvancementKind::Craft { recipes: vec![recipe(&["b"], "a")] },
+                ],
+            )],
+            vec![keepsake("", "raw"), keepsake("", "a"), keepsake("", "b")],
+        );
+
+        let tree = TechTree::build(&config);
+        let raw = config.find_possession_handle("", &"raw").unwrap();
+        let a = config.find_possession_handle("", &"a").unwrap();
+        let b = config.find_possession_handle("", &"b").unwrap();
+
+        let unreachable = tree.unreachable();
+        assert!(!unreachable.contains(&raw));
+        assert!(unreachable.contains(&a));
+        assert!(unreachable.contains(&b));
+    }
+}